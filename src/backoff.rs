@@ -0,0 +1,18 @@
+// Shared exponential backoff with jitter, used anywhere we retry a flaky
+// outbound request (the resolver's HTTP calls, Discord webhook delivery).
+
+use std::time::Duration;
+
+/// Computes an exponential backoff delay for `attempt` (0-indexed), with
+/// jitter mixed in so concurrent retries don't all wake up at once.
+///
+/// Crude jitter without pulling in a `rand` dependency: mixes the current
+/// time's subsecond millis into the delay.
+pub fn jittered_backoff(attempt: u32, base_ms: u64, jitter_ms: u64) -> Duration {
+    let base = base_ms.saturating_mul(1u64 << attempt.min(6));
+    let jitter = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_millis() as u64 % jitter_ms.max(1))
+        .unwrap_or(0);
+    Duration::from_millis(base + jitter)
+}
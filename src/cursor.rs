@@ -0,0 +1,74 @@
+// Persists the Jetstream cursor (the `time_us` of the last processed event)
+// across restarts, so a redeploy or crash resumes from where it left off
+// instead of replaying or skipping events.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+
+/// Where the last committed Jetstream cursor position is stored.
+#[async_trait]
+pub trait CursorStore: Send + Sync {
+    /// Loads the last persisted cursor, if any.
+    async fn load(&self) -> Result<Option<u64>>;
+
+    /// Persists `time_us` as the last committed cursor.
+    async fn save(&self, time_us: u64) -> Result<()>;
+}
+
+/// Stores the cursor as a single integer in a file, written atomically
+/// (write to a temp file, then rename) so a crash mid-write can't corrupt
+/// the stored value.
+pub struct FileCursorStore {
+    path: PathBuf,
+}
+
+impl FileCursorStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Builds a store from the `CURSOR_FILE` env var, defaulting to
+    /// `cursor.txt` in the working directory.
+    pub fn from_env() -> Self {
+        let path = std::env::var("CURSOR_FILE").unwrap_or_else(|_| "cursor.txt".to_string());
+        Self::new(path)
+    }
+
+    fn tmp_path(&self) -> PathBuf {
+        let mut tmp = self.path.clone();
+        tmp.set_extension("tmp");
+        tmp
+    }
+}
+
+#[async_trait]
+impl CursorStore for FileCursorStore {
+    async fn load(&self) -> Result<Option<u64>> {
+        if !Path::new(&self.path).exists() {
+            return Ok(None);
+        }
+        let contents = tokio::fs::read_to_string(&self.path)
+            .await
+            .with_context(|| format!("failed to read cursor file {}", self.path.display()))?;
+        let trimmed = contents.trim();
+        if trimmed.is_empty() {
+            return Ok(None);
+        }
+        let time_us = trimmed
+            .parse::<u64>()
+            .with_context(|| format!("cursor file {} contains invalid data", self.path.display()))?;
+        Ok(Some(time_us))
+    }
+
+    async fn save(&self, time_us: u64) -> Result<()> {
+        let tmp_path = self.tmp_path();
+        tokio::fs::write(&tmp_path, time_us.to_string())
+            .await
+            .with_context(|| format!("failed to write cursor temp file {}", tmp_path.display()))?;
+        tokio::fs::rename(&tmp_path, &self.path)
+            .await
+            .with_context(|| format!("failed to commit cursor file {}", self.path.display()))?;
+        Ok(())
+    }
+}
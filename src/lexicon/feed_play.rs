@@ -0,0 +1,62 @@
+// Typed record for `fm.teal.alpha.feed.play`, teal.fm's scrobble lexicon.
+
+use super::RecordHandler;
+use serde::Deserialize;
+use serde_json::Value;
+
+pub const NSID: &str = "fm.teal.alpha.feed.play";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Artist {
+    pub artist_name: String,
+    #[serde(default)]
+    pub artist_mbid: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Record {
+    pub track_name: String,
+    #[serde(default)]
+    pub artists: Vec<Artist>,
+    #[serde(default)]
+    pub release_name: Option<String>,
+    /// Track duration, in seconds.
+    #[serde(default)]
+    pub duration: Option<u64>,
+    #[serde(default)]
+    pub recording_mbid: Option<String>,
+    #[serde(default)]
+    pub release_mbid: Option<String>,
+    /// When the play was submitted, as an ISO 8601 timestamp.
+    #[serde(default)]
+    pub submitted_at: Option<String>,
+}
+
+impl Record {
+    /// Renders as "{track} by {artist}", matching the previous ad-hoc
+    /// formatting for backwards-compatible notification text.
+    pub fn describe(&self) -> String {
+        let artist = self
+            .artists
+            .first()
+            .map(|a| a.artist_name.as_str())
+            .unwrap_or("unknown artist");
+        format!("{} by {}", self.track_name, artist)
+    }
+}
+
+pub struct FeedPlayHandler;
+
+impl RecordHandler for FeedPlayHandler {
+    fn describe(&self, record: &Value) -> String {
+        match serde_json::from_value::<Record>(record.clone()) {
+            Ok(parsed) => parsed.describe(),
+            Err(e) => {
+                tracing::warn!(nsid = NSID, error = %e, "failed to parse record, falling back to 'unknown track'");
+                "unknown track".to_string()
+            }
+        }
+    }
+}
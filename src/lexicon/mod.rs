@@ -0,0 +1,52 @@
+// Typed lexicon records, replacing ad-hoc `serde_json::Value` digging.
+//
+// Each supported NSID gets its own submodule with a strongly-typed record
+// and a `RecordHandler` registered here. Unknown NSIDs fall back to the
+// dynamic path (log + raw JSON), mirroring flodgatt's split between a
+// type-safe event variant and a dynamic one.
+
+pub mod feed_play;
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Renders a raw record for a specific NSID into a short human-readable
+/// description, e.g. "T-Rex by Marc Bolan".
+pub trait RecordHandler: Send + Sync {
+    fn describe(&self, record: &Value) -> String;
+}
+
+/// Maps NSID -> typed handler, so new collections can be added without
+/// touching `main`.
+pub struct Registry {
+    handlers: HashMap<&'static str, Box<dyn RecordHandler>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        let mut handlers: HashMap<&'static str, Box<dyn RecordHandler>> = HashMap::new();
+        handlers.insert(feed_play::NSID, Box::new(feed_play::FeedPlayHandler));
+        Self { handlers }
+    }
+
+    /// Describes `record` for `nsid` using its registered typed handler.
+    /// When no handler is registered, logs the raw JSON and returns
+    /// "unknown record". A record that doesn't match the expected shape is
+    /// left to the handler's own fallback (the feed play handler logs the
+    /// parse error and returns "unknown track").
+    pub fn describe(&self, nsid: &str, record: &Value) -> String {
+        match self.handlers.get(nsid) {
+            Some(handler) => handler.describe(record),
+            None => {
+                tracing::warn!(nsid, %record, "no typed handler registered, falling back to raw JSON");
+                "unknown record".to_string()
+            }
+        }
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
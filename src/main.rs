@@ -1,27 +1,54 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use reqwest::Client;
 use rocketman::{
     connection::JetstreamConnection, handler, ingestion::LexiconIngestor,
     options::JetstreamOptions, types::event::Event,
 };
-use serde_json::{Value, json};
+use serde_json::Value;
 use std::{
     collections::HashMap,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
+mod backoff;
+mod cursor;
+mod lexicon;
+mod metrics;
+mod queue;
 mod resolve;
+mod sink;
+mod verify;
+
+use cursor::{CursorStore, FileCursorStore};
+use sink::{NotificationEvent, SinkSet};
+
+/// How often the in-memory cursor is flushed to the [`CursorStore`].
+const CURSOR_SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The one NSID this ingestor handles; kept as a constant since it's needed
+/// both for the Jetstream collection filter and to register the ingestor.
+const FEED_PLAY_NSID: &str = "fm.teal.alpha.feed.play";
 
 #[tokio::main]
 async fn main() {
     // Load environment variables from .env file
     dotenv::dotenv().ok();
 
+    tracing_subscriber::fmt::init();
+
+    // serve Prometheus metrics (events, sink latency/success, cache hit rate, reconnects, ...)
+    metrics::spawn_from_env();
+
+    // build the configured set of notification sinks (discord, slack, webhook, stdout, ...)
+    let sinks = Arc::new(SinkSet::from_env().expect("failed to configure notification sinks"));
+    // typed lexicon registry, NSID -> handler, with a dynamic fallback for unknown collections
+    let lexicon_registry = Arc::new(lexicon::Registry::new());
+
     // init the builder
     let opts = JetstreamOptions::builder()
         // your EXACT nsids
-        .wanted_collections(vec!["fm.teal.alpha.feed.play".to_string()])
+        .wanted_collections(vec![FEED_PLAY_NSID.to_string()])
         .build();
     // create the jetstream connector
     let jetstream = JetstreamConnection::new(opts);
@@ -30,12 +57,24 @@ async fn main() {
     let mut ingestors: HashMap<String, Box<dyn LexiconIngestor + Send + Sync>> = HashMap::new();
     ingestors.insert(
         // your EXACT nsid
-        "fm.teal.alpha.feed.play".to_string(),
-        Box::new(MyCoolIngestor),
+        FEED_PLAY_NSID.to_string(),
+        Box::new(MyCoolIngestor {
+            sinks,
+            lexicon_registry,
+        }),
     );
 
-    // tracks the last message we've processed
-    let cursor: Arc<Mutex<Option<u64>>> = Arc::new(Mutex::new(None));
+    // tracks the last message we've processed, resuming from wherever we
+    // left off on the previous run
+    let cursor_store = Arc::new(FileCursorStore::from_env());
+    let initial_cursor = match cursor_store.load().await {
+        Ok(value) => value,
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to load persisted cursor, starting fresh");
+            None
+        }
+    };
+    let cursor: Arc<Mutex<Option<u64>>> = Arc::new(Mutex::new(initial_cursor));
 
     // get channels
     let msg_rx = jetstream.get_msg_rx();
@@ -50,73 +89,171 @@ async fn main() {
                 handler::handle_message(message, &ingestors, reconnect_tx.clone(), c_cursor.clone())
                     .await
             {
-                eprintln!("Error processing message: {}", e);
+                tracing::error!(error = %e, "error processing message");
             };
         }
     });
 
-    // connect to jetstream
-    // retries internally, but may fail if there is an extreme error.
-    if let Err(e) = jetstream.connect(cursor.clone()).await {
-        eprintln!("Failed to connect to Jetstream: {}", e);
-        std::process::exit(1);
+    // periodically debounce-flush the cursor to disk, so we only lose at
+    // most CURSOR_SAVE_INTERVAL worth of progress on an unclean exit
+    let save_cursor = cursor.clone();
+    let save_store = cursor_store.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CURSOR_SAVE_INTERVAL);
+        loop {
+            interval.tick().await;
+            persist_cursor(&save_cursor, &save_store).await;
+        }
+    });
+
+    // save the cursor one last time on graceful shutdown so a redeploy
+    // doesn't replay or skip events
+    let shutdown_cursor = cursor.clone();
+    let shutdown_store = cursor_store.clone();
+    tokio::spawn(async move {
+        wait_for_shutdown_signal().await;
+        tracing::info!("shutdown signal received, persisting cursor");
+        persist_cursor(&shutdown_cursor, &shutdown_store).await;
+        std::process::exit(0);
+    });
+
+    // connect to jetstream. `connect` retries internally, but may still
+    // return on an extreme error; when it does, count it as a reconnect and
+    // try again rather than exiting outright.
+    loop {
+        if let Err(e) = jetstream.connect(cursor.clone()).await {
+            metrics::METRICS.record_reconnect();
+            tracing::error!(error = %e, "jetstream connection dropped, reconnecting");
+            persist_cursor(&cursor, &cursor_store).await;
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            continue;
+        }
+        break;
+    }
+}
+
+/// Saves the current cursor value, if one has been set, logging any error
+/// rather than panicking (a failed save just means we replay a bit more on
+/// the next restart).
+async fn persist_cursor(cursor: &Arc<Mutex<Option<u64>>>, store: &Arc<FileCursorStore>) {
+    let value = *cursor.lock().expect("cursor mutex poisoned");
+    if let Some(time_us) = value {
+        if let Err(e) = store.save(time_us).await {
+            tracing::warn!(error = %e, "failed to persist cursor");
+        }
+    }
+}
+
+/// Resolves once a SIGINT (ctrl-c) or, on unix, a SIGTERM is received.
+async fn wait_for_shutdown_signal() {
+    #[cfg(unix)]
+    {
+        let mut sigterm = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler");
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {},
+            _ = sigterm.recv() => {},
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
     }
 }
 
-pub struct MyCoolIngestor;
+pub struct MyCoolIngestor {
+    sinks: Arc<SinkSet>,
+    lexicon_registry: Arc<lexicon::Registry>,
+}
 
-/// A cool ingestor implementation. Will just print the message. Does not do verification.
+/// A cool ingestor implementation. Optionally verifies each commit against
+/// the author's PDS, renders the event, and fans it out to the configured
+/// sinks.
 #[async_trait]
 impl LexiconIngestor for MyCoolIngestor {
+    #[tracing::instrument(skip_all, fields(did = %message.did))]
     async fn ingest(&self, message: Event<Value>) -> Result<()> {
+        metrics::METRICS.record_event_received();
+
         // Only process Create operations, ignore Delete operations
-        if let Some(commit) = &message.commit {
-            if !matches!(commit.operation, rocketman::types::event::Operation::Create) {
-                return Ok(());
-            }
-        } else {
+        let Some(commit) = &message.commit else {
+            return Ok(());
+        };
+        if !matches!(commit.operation, rocketman::types::event::Operation::Create) {
             return Ok(());
         }
 
-        let client = Client::new();
-        let url = std::env::var("DISCORD_WEBHOOK_URL")
-            .expect("DISCORD_WEBHOOK_URL environment variable must be set");
-        
         // Get resolver app view URL from environment
         let resolver_app_view = std::env::var("RESOLVER_APP_VIEW")
             .unwrap_or_else(|_| "https://bsky.social".to_string());
-        
-        // Safely extract track name and artist from the record
-        let track_info = message
-            .commit
-            .as_ref()
-            .and_then(|commit| commit.record.as_ref())
-            .and_then(|record| {
-                let track_name = record.get("trackName")?.as_str()?;
-                let artists = record.get("artists")?.as_array()?;
-                let artist_name = artists.first()?.get("artistName")?.as_str()?;
-                Some(format!("{} by {}", track_name, artist_name))
-            })
-            .unwrap_or_else(|| "unknown track".to_string());
 
         // Resolve the handle from the DID
-        let handle = match resolve::resolve_identity(&message.did, &resolver_app_view).await {
-            Ok(resolved) => resolved.identity,
+        let resolved = resolve::resolve_identity(&message.did, &resolver_app_view).await;
+        let (handle, pds) = match &resolved {
+            Ok(resolved) => (resolved.identity.clone(), Some(resolved.pds.clone())),
             Err(e) => {
-                eprintln!("Failed to resolve handle for DID {}: {}", message.did, e);
+                tracing::warn!(did = %message.did, error = %e, "failed to resolve handle for DID");
                 // Fallback to showing the DID if resolution fails
-                message.did.clone()
+                (message.did.clone(), None)
             }
         };
 
-        let payload = json!({
-            "content": format!("{} is listening to {}", handle, track_info)
-        });
-        let response = client.post(url).json(&payload).send().await?;
+        if verify::verification_enabled() {
+            match pds {
+                Some(pds) => {
+                    let outcome = verify::verify_record(
+                        &pds,
+                        &message.did,
+                        &commit.collection,
+                        &commit.rkey,
+                        &commit.cid,
+                    )
+                    .await;
+                    match outcome {
+                        verify::VerifyOutcome::Verified => {}
+                        verify::VerifyOutcome::Mismatch => {
+                            tracing::warn!(did = %message.did, "dropping unverifiable event: record does not match PDS");
+                            metrics::METRICS.record_event_dropped();
+                            return Ok(());
+                        }
+                        verify::VerifyOutcome::PdsUnreachable => {
+                            if !verify::soft_fail_on_unreachable() {
+                                tracing::warn!(did = %message.did, "dropping event: PDS unreachable and hard-fail is enabled");
+                                metrics::METRICS.record_event_dropped();
+                                return Ok(());
+                            }
+                        }
+                    }
+                }
+                None => {
+                    // Couldn't resolve a PDS at all, so there's nothing to verify against.
+                    if !verify::soft_fail_on_unreachable() {
+                        metrics::METRICS.record_event_dropped();
+                        return Ok(());
+                    }
+                }
+            }
+        }
+
+        // Render the record through the typed lexicon registry, keyed by the
+        // commit's own collection so a second registered NSID doesn't need
+        // any changes here. Falls back to a dynamic description for unknown
+        // NSIDs or unexpected shapes.
+        let track_info = commit
+            .record
+            .as_ref()
+            .map(|record| self.lexicon_registry.describe(&commit.collection, record))
+            .unwrap_or_else(|| "unknown track".to_string());
+
+        let event = NotificationEvent {
+            message: format!("{} is listening to {}", handle, track_info),
+            handle,
+            track_info,
+            raw: commit.record.clone().unwrap_or(Value::Null),
+        };
+        self.sinks.deliver(&event).await;
+        metrics::METRICS.record_event_forwarded();
 
-        println!("{:?}", response.status());
-        println!("{:?}", message);
-        // Process message for default lexicon.
         Ok(())
     }
 }
@@ -0,0 +1,210 @@
+// Small observability layer: counters for the ingest pipeline, exposed as
+// Prometheus text format over a bare-bones HTTP endpoint. Kept dependency-free
+// (no metrics/axum crate) in keeping with the rest of this crate's DIY style.
+
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Sink delivery latencies above this are logged as a `tracing::warn` event,
+/// borrowing activitypub-federation's "warn when delivery is slow" idea.
+/// Configurable via `SLOW_DELIVERY_THRESHOLD_MS` (default 2000ms).
+fn slow_delivery_threshold() -> Duration {
+    let ms = std::env::var("SLOW_DELIVERY_THRESHOLD_MS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(2000);
+    Duration::from_millis(ms)
+}
+
+#[derive(Default)]
+struct SinkCounters {
+    delivered: AtomicU64,
+    failed: AtomicU64,
+    latency_ms_sum: AtomicU64,
+    latency_samples: AtomicU64,
+}
+
+pub struct Metrics {
+    events_received: AtomicU64,
+    events_forwarded: AtomicU64,
+    events_dropped: AtomicU64,
+    jetstream_reconnects: AtomicU64,
+    resolver_cache_hits: AtomicU64,
+    resolver_cache_misses: AtomicU64,
+    sinks: Mutex<HashMap<String, SinkCounters>>,
+}
+
+lazy_static! {
+    pub static ref METRICS: Metrics = Metrics::new();
+}
+
+impl Metrics {
+    fn new() -> Self {
+        Self {
+            events_received: AtomicU64::new(0),
+            events_forwarded: AtomicU64::new(0),
+            events_dropped: AtomicU64::new(0),
+            jetstream_reconnects: AtomicU64::new(0),
+            resolver_cache_hits: AtomicU64::new(0),
+            resolver_cache_misses: AtomicU64::new(0),
+            sinks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn record_event_received(&self) {
+        self.events_received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_event_forwarded(&self) {
+        self.events_forwarded.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_event_dropped(&self) {
+        self.events_dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_reconnect(&self) {
+        self.jetstream_reconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_cache_hit(&self) {
+        self.resolver_cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_cache_miss(&self) {
+        self.resolver_cache_misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records the outcome and latency of a single sink delivery attempt,
+    /// warning when it's slower than `SLOW_DELIVERY_THRESHOLD_MS`.
+    pub fn record_sink_delivery(&self, sink: &str, success: bool, latency: Duration) {
+        let mut sinks = self.sinks.lock().expect("metrics sinks mutex poisoned");
+        let counters = sinks.entry(sink.to_string()).or_default();
+        if success {
+            counters.delivered.fetch_add(1, Ordering::Relaxed);
+        } else {
+            counters.failed.fetch_add(1, Ordering::Relaxed);
+        }
+        counters
+            .latency_ms_sum
+            .fetch_add(latency.as_millis() as u64, Ordering::Relaxed);
+        counters.latency_samples.fetch_add(1, Ordering::Relaxed);
+        drop(sinks);
+
+        if latency >= slow_delivery_threshold() {
+            tracing::warn!(sink, ?latency, "sink delivery exceeded slow-delivery threshold");
+        }
+    }
+
+    /// Renders all counters as Prometheus exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP discordhose_events_received_total Jetstream events received.\n");
+        out.push_str("# TYPE discordhose_events_received_total counter\n");
+        out.push_str(&format!(
+            "discordhose_events_received_total {}\n",
+            self.events_received.load(Ordering::Relaxed)
+        ));
+
+        out.push_str("# HELP discordhose_events_forwarded_total Events forwarded to at least one sink.\n");
+        out.push_str("# TYPE discordhose_events_forwarded_total counter\n");
+        out.push_str(&format!(
+            "discordhose_events_forwarded_total {}\n",
+            self.events_forwarded.load(Ordering::Relaxed)
+        ));
+
+        out.push_str("# HELP discordhose_events_dropped_total Events dropped (failed verification, etc).\n");
+        out.push_str("# TYPE discordhose_events_dropped_total counter\n");
+        out.push_str(&format!(
+            "discordhose_events_dropped_total {}\n",
+            self.events_dropped.load(Ordering::Relaxed)
+        ));
+
+        out.push_str("# HELP discordhose_jetstream_reconnects_total Jetstream reconnect attempts.\n");
+        out.push_str("# TYPE discordhose_jetstream_reconnects_total counter\n");
+        out.push_str(&format!(
+            "discordhose_jetstream_reconnects_total {}\n",
+            self.jetstream_reconnects.load(Ordering::Relaxed)
+        ));
+
+        out.push_str("# HELP discordhose_resolver_cache_hits_total Resolver cache hits.\n");
+        out.push_str("# TYPE discordhose_resolver_cache_hits_total counter\n");
+        out.push_str(&format!(
+            "discordhose_resolver_cache_hits_total {}\n",
+            self.resolver_cache_hits.load(Ordering::Relaxed)
+        ));
+
+        out.push_str("# HELP discordhose_resolver_cache_misses_total Resolver cache misses.\n");
+        out.push_str("# TYPE discordhose_resolver_cache_misses_total counter\n");
+        out.push_str(&format!(
+            "discordhose_resolver_cache_misses_total {}\n",
+            self.resolver_cache_misses.load(Ordering::Relaxed)
+        ));
+
+        out.push_str("# HELP discordhose_sink_delivered_total Successful sink deliveries.\n");
+        out.push_str("# TYPE discordhose_sink_delivered_total counter\n");
+        out.push_str("# HELP discordhose_sink_failed_total Failed sink deliveries.\n");
+        out.push_str("# TYPE discordhose_sink_failed_total counter\n");
+        out.push_str("# HELP discordhose_sink_delivery_latency_ms_avg Average sink delivery latency.\n");
+        out.push_str("# TYPE discordhose_sink_delivery_latency_ms_avg gauge\n");
+
+        let sinks = self.sinks.lock().expect("metrics sinks mutex poisoned");
+        for (name, counters) in sinks.iter() {
+            let delivered = counters.delivered.load(Ordering::Relaxed);
+            let failed = counters.failed.load(Ordering::Relaxed);
+            let latency_sum = counters.latency_ms_sum.load(Ordering::Relaxed);
+            let latency_samples = counters.latency_samples.load(Ordering::Relaxed).max(1);
+            out.push_str(&format!(
+                "discordhose_sink_delivered_total{{sink=\"{name}\"}} {delivered}\n"
+            ));
+            out.push_str(&format!("discordhose_sink_failed_total{{sink=\"{name}\"}} {failed}\n"));
+            out.push_str(&format!(
+                "discordhose_sink_delivery_latency_ms_avg{{sink=\"{name}\"}} {}\n",
+                latency_sum / latency_samples
+            ));
+        }
+
+        out
+    }
+}
+
+/// Serves `GET /metrics` (and anything else, really - this is intentionally
+/// minimal) on `addr` until the process exits.
+pub async fn serve(addr: &str) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    tracing::info!(%addr, "metrics endpoint listening");
+    loop {
+        let (mut socket, _) = listener.accept().await?;
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1024];
+            // We don't care what was requested - always serve the metrics body.
+            let _ = socket.read(&mut buf).await;
+            let body = METRICS.render();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            if let Err(e) = socket.write_all(response.as_bytes()).await {
+                tracing::debug!(error = %e, "failed to write metrics response");
+            }
+        });
+    }
+}
+
+/// Reads `METRICS_ADDR` (default `0.0.0.0:9090`) and spawns the metrics
+/// server as a background task.
+pub fn spawn_from_env() {
+    let addr = std::env::var("METRICS_ADDR").unwrap_or_else(|_| "0.0.0.0:9090".to_string());
+    tokio::spawn(async move {
+        if let Err(e) = serve(&addr).await {
+            tracing::error!(error = %e, "metrics server exited");
+        }
+    });
+}
@@ -0,0 +1,90 @@
+// Turns best-effort fire-and-forget delivery into a durable, bounded
+// pipeline: the ingest path enqueues events and returns immediately, while a
+// dedicated sender task per sink drains the queue, optionally coalescing
+// bursts of plays into a single message before handing off to the sink
+// (which is itself responsible for honoring rate limits/backoff, see
+// `DiscordSink::deliver`).
+
+use crate::sink::{NotificationEvent, Sink};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Bound on how many undelivered events a single sink's queue will hold
+/// before producers block. Configurable via `DELIVERY_QUEUE_CAPACITY`.
+fn queue_capacity() -> usize {
+    std::env::var("DELIVERY_QUEUE_CAPACITY")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(256)
+}
+
+/// How long to keep collecting additional events into the same batch before
+/// handing it to the sink. `0` (the default) disables coalescing entirely.
+/// Configurable via `DELIVERY_COALESCE_WINDOW_MS`.
+fn coalesce_window() -> Duration {
+    let ms = std::env::var("DELIVERY_COALESCE_WINDOW_MS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    Duration::from_millis(ms)
+}
+
+/// A bounded, durable queue in front of a single [`Sink`].
+pub struct DeliveryQueue {
+    tx: mpsc::Sender<NotificationEvent>,
+    name: String,
+}
+
+impl DeliveryQueue {
+    /// Spawns the dedicated sender task and returns a handle producers can
+    /// enqueue onto.
+    pub fn spawn(sink: Arc<dyn Sink>) -> Self {
+        let name = sink.name().to_string();
+        let (tx, rx) = mpsc::channel(queue_capacity());
+        tokio::spawn(run_sender(sink, rx, coalesce_window()));
+        Self { tx, name }
+    }
+
+    /// Enqueues an event for delivery, applying backpressure if the queue is
+    /// full rather than dropping it.
+    pub async fn enqueue(&self, event: NotificationEvent) {
+        if self.tx.send(event).await.is_err() {
+            tracing::error!(sink = %self.name, "delivery queue is no longer accepting events");
+        }
+    }
+}
+
+async fn run_sender(
+    sink: Arc<dyn Sink>,
+    mut rx: mpsc::Receiver<NotificationEvent>,
+    coalesce_window: Duration,
+) {
+    while let Some(first) = rx.recv().await {
+        let mut batch = vec![first];
+
+        if !coalesce_window.is_zero() {
+            let deadline = tokio::time::Instant::now() + coalesce_window;
+            loop {
+                let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+                if remaining.is_zero() {
+                    break;
+                }
+                match tokio::time::timeout(remaining, rx.recv()).await {
+                    Ok(Some(event)) => batch.push(event),
+                    Ok(None) | Err(_) => break,
+                }
+            }
+        }
+
+        let event = NotificationEvent::coalesce(batch);
+        let started = tokio::time::Instant::now();
+        let result = sink.deliver(&event).await;
+        let latency = started.elapsed();
+
+        crate::metrics::METRICS.record_sink_delivery(sink.name(), result.is_ok(), latency);
+        if let Err(e) = result {
+            tracing::error!(sink = %sink.name(), error = %e, "sink failed to deliver event");
+        }
+    }
+}
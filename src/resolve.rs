@@ -4,8 +4,11 @@
 
 use lazy_static::lazy_static;
 use moka::future::Cache;
+use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
 use std::time::Duration;
+use thiserror::Error;
 
 // Cache for handle resolution - maps handle to DID
 type HandleCache = Cache<String, String>;
@@ -13,17 +16,106 @@ type HandleCache = Cache<String, String>;
 // Cache for DID documents - maps DID to DidDocument
 type DidDocumentCache = Cache<String, DidDocument>;
 
+// Negative cache - remembers identifiers that recently failed to resolve, so
+// a flood of events from an unresolvable DID doesn't retry the network on
+// every single one.
+type NegativeCache = Cache<String, ()>;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_RETRIES: u32 = 3;
+const NEGATIVE_CACHE_TTL: Duration = Duration::from_secs(30);
+
 // Global cache instances
 lazy_static::lazy_static! {
     static ref HANDLE_CACHE: HandleCache = Cache::builder()
         .time_to_live(Duration::from_secs(3600)) // 1 hour TTL
         .max_capacity(10000)
         .build();
-    
+
     static ref DID_DOCUMENT_CACHE: DidDocumentCache = Cache::builder()
         .time_to_live(Duration::from_secs(3600)) // 1 hour TTL
         .max_capacity(10000)
         .build();
+
+    static ref NEGATIVE_CACHE: NegativeCache = Cache::builder()
+        .time_to_live(NEGATIVE_CACHE_TTL)
+        .max_capacity(10000)
+        .build();
+}
+
+lazy_static! {
+    /// Shared timeout-bound client, also used by `verify::verify_record` so
+    /// PDS lookups get the same robustness as handle/DID resolution.
+    pub(crate) static ref HTTP_CLIENT: Client = Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .expect("failed to build resolver HTTP client");
+}
+
+/// Everything that can go wrong resolving a handle/DID to a [`ResolvedIdentity`].
+#[derive(Debug, Error)]
+pub enum ResolveError {
+    #[error("'{0}' is not a valid did:web domain")]
+    InvalidDidWebDomain(String),
+    #[error("unsupported DID method: {0}")]
+    UnsupportedDidMethod(String),
+    #[error("could not resolve handle '{0}' to a DID")]
+    HandleResolutionFailed(String),
+    #[error("DID document for '{0}' has no atproto PDS service entry")]
+    NoPdsEndpoint(String),
+    #[error("request to {0} failed after {1} attempts: {2}")]
+    RequestFailed(String, u32, reqwest::Error),
+    #[error("'{0}' failed to resolve recently; not retrying until the negative cache entry expires")]
+    RecentlyFailed(String),
+}
+
+/// Returns the PLC directory base URL, configurable via `PLC_DIRECTORY_URL`
+/// (e.g. for self-hosted or regional mirrors) rather than hardcoding
+/// `https://plc.directory`.
+fn plc_directory_url() -> String {
+    std::env::var("PLC_DIRECTORY_URL").unwrap_or_else(|_| "https://plc.directory".to_string())
+}
+
+/// Fetches and deserializes JSON from `url`, retrying transient failures
+/// (timeouts, connect errors, 5xx) with bounded exponential backoff. Client
+/// errors (4xx) are not retried.
+pub(crate) async fn get_json<T: DeserializeOwned>(url: &str) -> Result<T, ResolveError> {
+    let mut last_err = None;
+    for attempt in 0..=MAX_RETRIES {
+        if attempt > 0 {
+            tokio::time::sleep(crate::backoff::jittered_backoff(attempt, 200, 100)).await;
+        }
+
+        match HTTP_CLIENT.get(url).send().await {
+            Ok(response) => {
+                let status = response.status();
+                if status.is_client_error() {
+                    // Not going to succeed on retry - bail immediately.
+                    return Err(ResolveError::RequestFailed(
+                        url.to_string(),
+                        attempt + 1,
+                        response
+                            .error_for_status()
+                            .expect_err("client error status should produce an error"),
+                    ));
+                }
+                match response.error_for_status() {
+                    Ok(response) => match response.json::<T>().await {
+                        Ok(value) => return Ok(value),
+                        Err(e) => last_err = Some(e),
+                    },
+                    Err(e) => last_err = Some(e),
+                }
+            }
+            Err(e) => last_err = Some(e),
+        }
+    }
+
+    Err(ResolveError::RequestFailed(
+        url.to_string(),
+        MAX_RETRIES + 1,
+        last_err.expect("loop always sets last_err before exhausting retries"),
+    ))
 }
 
 // should be same as regex /^did:[a-z]+:[\S\s]+/
@@ -88,77 +180,87 @@ fn is_valid_domain(domain: &str) -> bool {
     true
 }
 
-async fn resolve_handle(handle: &str, resolver_app_view: &str) -> Result<String, reqwest::Error> {
+async fn resolve_handle(handle: &str, resolver_app_view: &str) -> Result<String, ResolveError> {
+    let negative_key = format!("handle:{handle}");
+    if NEGATIVE_CACHE.get(&negative_key).await.is_some() {
+        return Err(ResolveError::HandleResolutionFailed(handle.to_string()));
+    }
+
     // Check cache first
     if let Some(cached_did) = HANDLE_CACHE.get(handle).await {
-        println!("🎯 Cache HIT for handle: {} -> {}", handle, cached_did);
+        crate::metrics::METRICS.record_cache_hit();
         return Ok(cached_did);
     }
-
-    println!("❌ Cache MISS for handle: {}, resolving from API", handle);
+    crate::metrics::METRICS.record_cache_miss();
 
     // If not in cache, resolve from API
-    let res = reqwest::get(format!(
+    let url = format!(
         "{}/xrpc/com.atproto.identity.resolveHandle?handle={}",
         resolver_app_view, handle
-    ))
-    .await?
-    .json::<ResolvedHandle>()
-    .await?;
+    );
+    let res: ResolvedHandle = match get_json(&url).await {
+        Ok(res) => res,
+        Err(e) => {
+            NEGATIVE_CACHE.insert(negative_key, ()).await;
+            return Err(e);
+        }
+    };
 
     let did = res.did;
-    
-    // Cache the result
     HANDLE_CACHE.insert(handle.to_string(), did.clone()).await;
-    println!("💾 Cached handle resolution: {} -> {}", handle, did);
-    
+
     Ok(did)
 }
 
-async fn get_did_doc(did: &str) -> Result<DidDocument, reqwest::Error> {
+async fn get_did_doc(did: &str) -> Result<DidDocument, ResolveError> {
+    let negative_key = format!("did:{did}");
+    if NEGATIVE_CACHE.get(&negative_key).await.is_some() {
+        return Err(ResolveError::RecentlyFailed(did.to_string()));
+    }
+
     // Check cache first
     if let Some(cached_doc) = DID_DOCUMENT_CACHE.get(did).await {
-        println!("🎯 Cache HIT for DID document: {}", did);
+        crate::metrics::METRICS.record_cache_hit();
         return Ok(cached_doc);
     }
-
-    println!("❌ Cache MISS for DID document: {}, resolving from API", did);
+    crate::metrics::METRICS.record_cache_miss();
 
     // If not in cache, resolve from API
     // get the specific did spec
     // did:plc:abcd1e -> plc
     let parts: Vec<&str> = did.split(':').collect();
-    let spec = parts[1];
+    let spec = parts.get(1).copied().unwrap_or("");
     let doc = match spec {
-        "plc" => {
-            println!("📡 Fetching DID document from PLC directory for: {}", did);
-            let res: DidDocument = reqwest::get(format!("https://plc.directory/{}", did))
-                .await?
-                .error_for_status()?
-                .json()
-                .await?;
-            res
-        }
+        "plc" => match get_json::<DidDocument>(&format!("{}/{}", plc_directory_url(), did)).await {
+            Ok(doc) => doc,
+            Err(e) => {
+                NEGATIVE_CACHE.insert(negative_key, ()).await;
+                return Err(e);
+            }
+        },
         "web" => {
-            if !is_valid_domain(parts[2]) {
-                todo!("Error for domain in did:web is not valid");
-            };
-            let ident = parts[2];
-            println!("📡 Fetching DID document from web domain: {}", ident);
-            let res = reqwest::get(format!("https://{}/.well-known/did.json", ident))
-                .await?
-                .error_for_status()?
-                .json()
-                .await?;
-            res
+            let domain = parts.get(2).copied().unwrap_or("");
+            if !is_valid_domain(domain) {
+                NEGATIVE_CACHE.insert(negative_key, ()).await;
+                return Err(ResolveError::InvalidDidWebDomain(domain.to_string()));
+            }
+            match get_json::<DidDocument>(&format!("https://{}/.well-known/did.json", domain)).await {
+                Ok(doc) => doc,
+                Err(e) => {
+                    NEGATIVE_CACHE.insert(negative_key, ()).await;
+                    return Err(e);
+                }
+            }
+        }
+        other => {
+            NEGATIVE_CACHE.insert(negative_key, ()).await;
+            return Err(ResolveError::UnsupportedDidMethod(other.to_string()));
         }
-        _ => todo!("Identifier not supported"),
     };
 
     // Cache the result
     DID_DOCUMENT_CACHE.insert(did.to_string(), doc.clone()).await;
-    println!("💾 Cached DID document: {}", did);
-    
+
     Ok(doc)
 }
 
@@ -183,61 +285,45 @@ fn extract_handle_from_doc(doc: &DidDocument) -> Option<String> {
         if also_known_as.starts_with("at://") {
             // Extract handle from "at://handle.domain" format
             let handle = also_known_as.strip_prefix("at://")?;
-            println!("🎯 Found handle in alsoKnownAs: {} -> {}", also_known_as, handle);
             return Some(handle.to_string());
         }
     }
     None
 }
 
+#[tracing::instrument(skip(resolver_app_view))]
 pub async fn resolve_identity(
     id: &str,
     resolver_app_view: &str,
-) -> Result<ResolvedIdentity, reqwest::Error> {
-    println!("🔍 Resolving identity: {}", id);
-    
+) -> Result<ResolvedIdentity, ResolveError> {
     // is our identifier a did
     let did = if is_did(id) {
-        println!("✅ Input is already a DID: {}", id);
-        id
+        id.to_string()
     } else {
-        println!("🔗 Input is a handle, resolving to DID: {}", id);
         // our id must be either invalid or a handle
-        if let Ok(res) = resolve_handle(id, resolver_app_view).await {
-            &res.clone()
-        } else {
-            todo!("Error type for could not resolve handle")
-        }
+        resolve_handle(id, resolver_app_view).await?
     };
 
-    let doc = get_did_doc(did).await?;
-    let pds = get_pds_endpoint(&doc);
-
-    if pds.is_none() {
-        todo!("Error for could not find PDS")
-    }
-
-    // Extract handle from alsoKnownAs list
-    let handle = extract_handle_from_doc(&doc).unwrap_or_else(|| {
-        println!("⚠️  No handle found in alsoKnownAs, using original input: {}", id);
-        id.to_string()
-    });
+    let doc = get_did_doc(&did).await?;
+    let pds =
+        get_pds_endpoint(&doc).ok_or_else(|| ResolveError::NoPdsEndpoint(did.clone()))?;
 
-    println!("✅ Successfully resolved identity: {} -> {} (handle: {}) (PDS: {})", 
-             id, did, handle, pds.as_ref().unwrap().service_endpoint);
+    // Extract handle from alsoKnownAs list, falling back to the original input
+    let handle = extract_handle_from_doc(&doc).unwrap_or_else(|| id.to_string());
 
-    return Ok(ResolvedIdentity {
-        did: did.to_owned(),
+    Ok(ResolvedIdentity {
+        did,
         doc,
         identity: handle,
-        pds: pds.unwrap().service_endpoint,
-    });
+        pds: pds.service_endpoint,
+    })
 }
 
 /// Clear all cached handle resolutions and DID documents
 pub async fn clear_cache() {
     HANDLE_CACHE.invalidate_all();
     DID_DOCUMENT_CACHE.invalidate_all();
+    NEGATIVE_CACHE.invalidate_all();
 }
 
 /// Get cache statistics for monitoring
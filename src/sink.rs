@@ -0,0 +1,401 @@
+use crate::backoff::jittered_backoff;
+use crate::queue::DeliveryQueue;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Max attempts `DiscordSink` will make for a single delivery before giving
+/// up, covering both 429s and 5xx responses.
+const MAX_DISCORD_ATTEMPTS: u32 = 5;
+
+/// A rendered notification, decoupled from whatever transport ends up delivering it.
+///
+/// Ingestors build one of these once per event; sinks are only responsible for
+/// getting `message` (and, where supported, `raw`) to their destination.
+#[derive(Debug, Clone)]
+pub struct NotificationEvent {
+    /// Human-readable handle or DID of the listener.
+    pub handle: String,
+    /// Human-readable "track by artist" description.
+    pub track_info: String,
+    /// Plain-text message, e.g. "{handle} is listening to {track_info}".
+    pub message: String,
+    /// The original record, kept around for sinks that want structured data
+    /// (e.g. the JSON webhook and stdout/NDJSON sinks).
+    pub raw: serde_json::Value,
+}
+
+impl NotificationEvent {
+    /// Coalesces several events that arrived within the same batching
+    /// window into a single message, one line per event. Returns the event
+    /// unchanged if `events` has exactly one element.
+    ///
+    /// Events are grouped by `handle` first: a batch is nearly always all
+    /// one listener's plays, but different users can land in the same
+    /// window, and collapsing their `handle`/`track_info` together would
+    /// misattribute tracks in the structured (webhook/stdout) payload.
+    pub fn coalesce(mut events: Vec<NotificationEvent>) -> NotificationEvent {
+        if events.len() == 1 {
+            return events.remove(0);
+        }
+
+        let message = events
+            .iter()
+            .map(|e| e.message.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let raw = serde_json::Value::Array(events.iter().map(|e| e.raw.clone()).collect());
+
+        let mut by_handle: Vec<(String, Vec<&NotificationEvent>)> = Vec::new();
+        for event in &events {
+            match by_handle.iter_mut().find(|(handle, _)| *handle == event.handle) {
+                Some((_, grouped)) => grouped.push(event),
+                None => by_handle.push((event.handle.clone(), vec![event])),
+            }
+        }
+
+        let (handle, track_info) = if let [(handle, grouped)] = by_handle.as_slice() {
+            // Common case: every event in the batch is the same listener.
+            let track_info = grouped
+                .iter()
+                .map(|e| e.track_info.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            (handle.clone(), track_info)
+        } else {
+            // Mixed batch: don't attribute every track to one handle.
+            let track_info = by_handle
+                .iter()
+                .map(|(handle, grouped)| {
+                    let tracks = grouped
+                        .iter()
+                        .map(|e| e.track_info.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!("{handle}: {tracks}")
+                })
+                .collect::<Vec<_>>()
+                .join("; ");
+            (String::new(), track_info)
+        };
+
+        NotificationEvent {
+            handle,
+            track_info,
+            message,
+            raw,
+        }
+    }
+}
+
+/// A destination a [`NotificationEvent`] can be delivered to.
+///
+/// Implementations should treat delivery failures as recoverable: return an
+/// `Err` and let the caller decide whether to retry, log, or drop.
+#[async_trait]
+pub trait Sink: Send + Sync {
+    /// Short identifier used in logs, e.g. "discord" or "stdout".
+    fn name(&self) -> &str;
+
+    async fn deliver(&self, event: &NotificationEvent) -> Result<()>;
+}
+
+/// Posts to a Discord incoming webhook.
+pub struct DiscordSink {
+    client: Client,
+    webhook_url: String,
+    /// When Discord's rate-limit headers say we've exhausted our bucket,
+    /// the instant we should wait until before the *next* delivery attempt
+    /// (this sink's own or a later event's), so we throttle proactively
+    /// instead of waiting to be told via a 429.
+    rate_limited_until: tokio::sync::Mutex<Option<tokio::time::Instant>>,
+}
+
+impl DiscordSink {
+    pub fn new(webhook_url: String) -> Self {
+        Self {
+            client: Client::new(),
+            webhook_url,
+            rate_limited_until: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// Reads Discord's `X-RateLimit-Remaining`/`X-RateLimit-Reset` headers
+    /// and, if the bucket is exhausted, records when it's safe to send
+    /// again so the *next* call can wait up front rather than finding out
+    /// via a 429.
+    async fn note_rate_limit_headers(&self, response: &reqwest::Response) {
+        let remaining = response
+            .headers()
+            .get("X-RateLimit-Remaining")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u32>().ok());
+        if remaining != Some(0) {
+            return;
+        }
+
+        let Some(reset_at) = response
+            .headers()
+            .get("X-RateLimit-Reset")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<f64>().ok())
+        else {
+            return;
+        };
+
+        let now_epoch = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        let wait = Duration::from_secs_f64((reset_at - now_epoch).max(0.0));
+        *self.rate_limited_until.lock().await = Some(tokio::time::Instant::now() + wait);
+    }
+}
+
+#[async_trait]
+impl Sink for DiscordSink {
+    fn name(&self) -> &str {
+        "discord"
+    }
+
+    /// Honors Discord's `429` responses (reading `retry_after` from the JSON
+    /// body, falling back to headers), proactively throttles based on the
+    /// `X-RateLimit-Remaining`/`X-RateLimit-Reset` headers so we don't need
+    /// to hit a 429 to learn we're out of budget, and backs off with jitter
+    /// on 5xx, making up to `MAX_DISCORD_ATTEMPTS` attempts in total.
+    async fn deliver(&self, event: &NotificationEvent) -> Result<()> {
+        let payload = json!({ "content": event.message });
+
+        for attempt in 0..MAX_DISCORD_ATTEMPTS {
+            if let Some(until) = self.rate_limited_until.lock().await.take() {
+                tokio::time::sleep_until(until).await;
+            }
+
+            let response = self
+                .client
+                .post(&self.webhook_url)
+                .json(&payload)
+                .send()
+                .await
+                .context("failed to POST to Discord webhook")?;
+
+            self.note_rate_limit_headers(&response).await;
+
+            let status = response.status();
+            if status.is_success() {
+                return Ok(());
+            }
+
+            if status == StatusCode::TOO_MANY_REQUESTS {
+                let retry_after = response
+                    .headers()
+                    .get("Retry-After")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.parse::<f64>().ok());
+                let body: Option<serde_json::Value> = response.json().await.ok();
+                let retry_after = body
+                    .as_ref()
+                    .and_then(|b| b.get("retry_after"))
+                    .and_then(|v| v.as_f64())
+                    .or(retry_after)
+                    .unwrap_or(1.0);
+                tracing::warn!(
+                    retry_after,
+                    attempt = attempt + 1,
+                    max_attempts = MAX_DISCORD_ATTEMPTS,
+                    "Discord webhook rate-limited us"
+                );
+                tokio::time::sleep(Duration::from_secs_f64(retry_after)).await;
+                continue;
+            }
+
+            if status.is_server_error() {
+                let delay = jittered_backoff(attempt, 500, 250);
+                tracing::warn!(
+                    %status,
+                    ?delay,
+                    attempt = attempt + 1,
+                    max_attempts = MAX_DISCORD_ATTEMPTS,
+                    "Discord webhook returned a server error, backing off"
+                );
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+
+            anyhow::bail!("Discord webhook returned {status}");
+        }
+
+        anyhow::bail!("Discord webhook delivery failed after {MAX_DISCORD_ATTEMPTS} attempts")
+    }
+}
+
+/// Posts to a Slack incoming webhook.
+pub struct SlackSink {
+    client: Client,
+    webhook_url: String,
+}
+
+impl SlackSink {
+    pub fn new(webhook_url: String) -> Self {
+        Self {
+            client: Client::new(),
+            webhook_url,
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for SlackSink {
+    fn name(&self) -> &str {
+        "slack"
+    }
+
+    async fn deliver(&self, event: &NotificationEvent) -> Result<()> {
+        let payload = json!({ "text": event.message });
+        let response = self
+            .client
+            .post(&self.webhook_url)
+            .json(&payload)
+            .send()
+            .await
+            .context("failed to POST to Slack webhook")?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Slack webhook returned {}", response.status());
+        }
+        Ok(())
+    }
+}
+
+/// Posts the raw event as JSON to an arbitrary webhook, for consumers that
+/// don't speak Discord/Slack's message formats.
+pub struct JsonWebhookSink {
+    client: Client,
+    url: String,
+}
+
+impl JsonWebhookSink {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: Client::new(),
+            url,
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for JsonWebhookSink {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    async fn deliver(&self, event: &NotificationEvent) -> Result<()> {
+        let payload = json!({
+            "handle": event.handle,
+            "track_info": event.track_info,
+            "message": event.message,
+            "record": event.raw,
+        });
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&payload)
+            .send()
+            .await
+            .context("failed to POST to JSON webhook")?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("JSON webhook returned {}", response.status());
+        }
+        Ok(())
+    }
+}
+
+/// Writes one NDJSON line per event to stdout, for piping into other tools.
+pub struct StdoutSink;
+
+#[async_trait]
+impl Sink for StdoutSink {
+    fn name(&self) -> &str {
+        "stdout"
+    }
+
+    async fn deliver(&self, event: &NotificationEvent) -> Result<()> {
+        let line = json!({
+            "handle": event.handle,
+            "track_info": event.track_info,
+            "message": event.message,
+            "record": event.raw,
+        });
+        let mut stdout = std::io::stdout();
+        writeln!(stdout, "{}", line).context("failed to write NDJSON line to stdout")?;
+        Ok(())
+    }
+}
+
+/// Fans a single [`NotificationEvent`] out to every configured [`Sink`],
+/// each behind its own bounded [`DeliveryQueue`] so a slow or rate-limited
+/// sink doesn't hold up delivery to the others. Once a sink's queue is full,
+/// enqueueing (and with it the ingest path) waits for room.
+pub struct SinkSet {
+    queues: Vec<DeliveryQueue>,
+}
+
+impl SinkSet {
+    pub fn new(sinks: Vec<Arc<dyn Sink>>) -> Self {
+        let queues = sinks.into_iter().map(DeliveryQueue::spawn).collect();
+        Self { queues }
+    }
+
+    /// Builds the active sink set from environment variables.
+    ///
+    /// `NOTIFICATION_SINKS` is a comma-separated list of sink names to
+    /// enable (`discord`, `slack`, `webhook`, `stdout`). Each sink reads its
+    /// own destination from its usual env var (`DISCORD_WEBHOOK_URL`,
+    /// `SLACK_WEBHOOK_URL`, `WEBHOOK_URL`). Defaults to `discord` alone,
+    /// matching the previous hardcoded behavior.
+    pub fn from_env() -> Result<Self> {
+        let configured = std::env::var("NOTIFICATION_SINKS").unwrap_or_else(|_| "discord".to_string());
+
+        let mut sinks: Vec<Arc<dyn Sink>> = Vec::new();
+        for name in configured.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+            match name {
+                "discord" => {
+                    let url = std::env::var("DISCORD_WEBHOOK_URL")
+                        .context("DISCORD_WEBHOOK_URL must be set to use the discord sink")?;
+                    sinks.push(Arc::new(DiscordSink::new(url)));
+                }
+                "slack" => {
+                    let url = std::env::var("SLACK_WEBHOOK_URL")
+                        .context("SLACK_WEBHOOK_URL must be set to use the slack sink")?;
+                    sinks.push(Arc::new(SlackSink::new(url)));
+                }
+                "webhook" => {
+                    let url = std::env::var("WEBHOOK_URL")
+                        .context("WEBHOOK_URL must be set to use the webhook sink")?;
+                    sinks.push(Arc::new(JsonWebhookSink::new(url)));
+                }
+                "stdout" => sinks.push(Arc::new(StdoutSink)),
+                other => anyhow::bail!("unknown notification sink: {other}"),
+            }
+        }
+
+        Ok(Self::new(sinks))
+    }
+
+    /// Enqueues `event` for delivery to every sink's queue. Returns as soon
+    /// as the event has been handed off to all of them; actual delivery (and
+    /// any retry/backoff) happens on each sink's dedicated sender task.
+    ///
+    /// The enqueues themselves are fanned out concurrently rather than
+    /// awaited one at a time, so a sink whose queue is backed up (e.g.
+    /// Discord mid-rate-limit) can't delay handoff to the others.
+    pub async fn deliver(&self, event: &NotificationEvent) {
+        let enqueues = self.queues.iter().map(|queue| queue.enqueue(event.clone()));
+        futures::future::join_all(enqueues).await;
+    }
+}
@@ -0,0 +1,110 @@
+// Verifies that a Jetstream "Create" commit really was written to the
+// author's PDS before we let it reach a sink. Protects against a forged
+// Jetstream relay or a spoofed event claiming "X is listening to Y".
+
+use crate::resolve::{self, ResolveError};
+use lazy_static::lazy_static;
+use moka::future::Cache;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Negative result cache: `did|collection|rkey` -> rejected. Keeps a flood of
+/// bad events from hammering the PDS with repeat lookups.
+type NegativeCache = Cache<String, ()>;
+
+lazy_static! {
+    static ref NEGATIVE_CACHE: NegativeCache = Cache::builder()
+        .time_to_live(Duration::from_secs(30))
+        .max_capacity(10_000)
+        .build();
+}
+
+/// Outcome of attempting to verify a commit against its author's PDS.
+#[derive(Debug, PartialEq, Eq)]
+pub enum VerifyOutcome {
+    /// The record on the PDS matches the commit's CID; safe to forward.
+    Verified,
+    /// The record on the PDS has a different CID, or doesn't exist; drop.
+    Mismatch,
+    /// The PDS could not be reached. Caller decides whether that's fatal
+    /// based on `VERIFY_PDS_SOFT_FAIL`.
+    PdsUnreachable,
+}
+
+#[derive(Deserialize)]
+struct GetRecordResponse {
+    cid: String,
+}
+
+/// Whether an unreachable PDS should be treated as "pass through" (soft
+/// fail, the default) or "drop" (hard fail). Controlled by the
+/// `VERIFY_PDS_SOFT_FAIL` env var (`true`/`false`, default `true`).
+pub fn soft_fail_on_unreachable() -> bool {
+    std::env::var("VERIFY_PDS_SOFT_FAIL")
+        .map(|v| v != "false")
+        .unwrap_or(true)
+}
+
+/// Whether verification is enabled at all. Controlled by
+/// `VERIFY_PDS_RECORDS` (`true`/`false`, default `false` to match prior
+/// behavior).
+pub fn verification_enabled() -> bool {
+    std::env::var("VERIFY_PDS_RECORDS")
+        .map(|v| v == "true")
+        .unwrap_or(false)
+}
+
+/// Fetches `repo=<did>, collection, rkey` from the author's PDS via
+/// `com.atproto.repo.getRecord` and compares its CID against `expected_cid`
+/// from the Jetstream event.
+#[tracing::instrument(skip(expected_cid))]
+pub async fn verify_record(
+    pds: &str,
+    did: &str,
+    collection: &str,
+    rkey: &str,
+    expected_cid: &str,
+) -> VerifyOutcome {
+    let cache_key = format!("{did}|{collection}|{rkey}");
+    if NEGATIVE_CACHE.get(&cache_key).await.is_some() {
+        return VerifyOutcome::Mismatch;
+    }
+
+    let url = format!(
+        "{}/xrpc/com.atproto.repo.getRecord?repo={}&collection={}&rkey={}",
+        pds.trim_end_matches('/'),
+        did,
+        collection,
+        rkey
+    );
+
+    // Routed through the resolver's timeout + bounded-retry HTTP helper, so a
+    // PDS that never responds can't wedge the single-consumer ingest loop.
+    let record: GetRecordResponse = match resolve::get_json(&url).await {
+        Ok(record) => record,
+        Err(ResolveError::RequestFailed(_, _, e)) if e.status().is_some_and(|s| s.is_client_error()) => {
+            tracing::warn!(pds, did, collection, rkey, "PDS has no matching record");
+            NEGATIVE_CACHE.insert(cache_key, ()).await;
+            return VerifyOutcome::Mismatch;
+        }
+        Err(e) => {
+            tracing::warn!(pds, did, collection, rkey, error = %e, "failed to reach PDS to verify record");
+            return VerifyOutcome::PdsUnreachable;
+        }
+    };
+
+    if record.cid == expected_cid {
+        VerifyOutcome::Verified
+    } else {
+        tracing::warn!(
+            did,
+            collection,
+            rkey,
+            expected_cid,
+            actual_cid = %record.cid,
+            "CID mismatch verifying record against PDS"
+        );
+        NEGATIVE_CACHE.insert(cache_key, ()).await;
+        VerifyOutcome::Mismatch
+    }
+}